@@ -0,0 +1,33 @@
+/// Controls how aggressively the keyspace fsyncs its journal.
+///
+/// Set via `Config`. Regardless of mode, writes are always crash-safe once
+/// they're in the journal - this only controls when (or whether) the
+/// journal is fsynced, i.e. how much data could be lost if the *process*
+/// (not just an individual write) crashes or the machine loses power.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DurabilityMode {
+    /// Fsync the journal on every commit, via [`crate::Keyspace::persist`].
+    ///
+    /// Strongest durability, but 100-1000x slower than the other modes,
+    /// since every commit pays for its own fsync.
+    SyncEveryCommit,
+
+    /// Buffer commits into flush epochs and let the background flush
+    /// thread fsync once per epoch, satisfying every commit in that epoch
+    /// at once (group commit).
+    ///
+    /// This is the default: it keeps most of the throughput of no fsyncing
+    /// at all, at the cost of losing at most one epoch's worth of commits
+    /// on a crash.
+    #[default]
+    PeriodicEpochFlush,
+
+    /// Never fsync the journal explicitly; rely on the OS to eventually
+    /// flush dirty pages.
+    ///
+    /// Fastest, but records only reach the journal file once the journal's
+    /// current block fills up, so even a process exit can lose the most
+    /// recent commits, and a machine crash can lose an arbitrary amount of
+    /// recently committed data.
+    NoSync,
+}
@@ -0,0 +1,128 @@
+use crate::{
+    durability::DurabilityMode, group_commit::GroupCommit, journal::Journal, keyspace::Partitions,
+};
+use std::collections::HashMap;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, RwLock,
+};
+use std::time::Duration;
+
+/// Default interval at which the background flush thread performs a group
+/// commit fsync, for keyspaces in [`crate::DurabilityMode::PeriodicEpochFlush`].
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Minimum size the active journal must reach before the flush thread will
+/// force every partition's memtable to flush and compact it.
+///
+/// Gating on size (rather than compacting on every wake-up of this thread)
+/// keeps a quiet keyspace from flushing tiny, mostly-empty memtables into a
+/// flood of tiny segments every `DEFAULT_FLUSH_INTERVAL`.
+const COMPACTION_SIZE_THRESHOLD: u64 = 4 * 1024 * 1024;
+
+/// Background thread that drives [`GroupCommit`]'s epoch fsyncs.
+///
+/// Spawned once per open keyspace. Dropping the handle stops the loop and
+/// joins the thread, so it always exits cleanly when the keyspace does.
+pub struct FlushThread {
+    stop: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl FlushThread {
+    pub fn spawn(
+        journal: Arc<Journal>,
+        partitions: Arc<RwLock<Partitions>>,
+        group_commit: Arc<GroupCommit>,
+        durability: DurabilityMode,
+        interval: Duration,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_signal = stop.clone();
+
+        let handle = std::thread::Builder::new()
+            .name("fjall-flush".into())
+            .spawn(move || {
+                while !stop_signal.load(Ordering::Acquire) {
+                    std::thread::sleep(interval);
+
+                    // Only `PeriodicEpochFlush` wants this thread to fsync
+                    // on its own: `SyncEveryCommit` already fsyncs inline
+                    // in `Keyspace::persist`, so doing it here too would
+                    // double-fsync every commit, and `NoSync` promises it
+                    // will never fsync the journal at all.
+                    if durability == DurabilityMode::PeriodicEpochFlush {
+                        if let Err(error) = group_commit.advance(&journal) {
+                            log::error!("Flush thread failed to fsync journal: {error:?}");
+                        }
+                    }
+
+                    // `NoSync` promises to never fsync the journal
+                    // explicitly, and compacting can't honor that -
+                    // `Journal::compact` persists both the active file (to
+                    // capture every record under consideration) and the
+                    // rewritten one. So journal GC simply doesn't run in
+                    // this mode; the journal only shrinks again once the
+                    // keyspace is reopened under a mode that allows it.
+                    if durability == DurabilityMode::NoSync {
+                        continue;
+                    }
+
+                    match journal.size() {
+                        Ok(size) if size < COMPACTION_SIZE_THRESHOLD => continue,
+                        Ok(_) => {}
+                        Err(error) => {
+                            log::error!("Failed to read journal size: {error:?}");
+                            continue;
+                        }
+                    }
+
+                    // Reclaim journal space: block-flush every loaded
+                    // partition's memtable to disk, then drop any journal
+                    // record already covered by that flush. `tree.flush()`
+                    // blocks until its memtable is durably on disk and
+                    // returns the seqno watermark that's now safe to use,
+                    // unlike `get_next_seqno` (which also counts unflushed
+                    // writes and would let GC drop records with no durable
+                    // copy yet).
+                    let partitions = partitions.read().expect("lock is poisoned");
+                    let mut flushed_seqno = HashMap::with_capacity(partitions.len());
+
+                    for (name, tree) in partitions.iter() {
+                        match tree.flush() {
+                            Ok(watermark) => {
+                                flushed_seqno.insert(name.clone(), watermark);
+                            }
+                            Err(error) => {
+                                log::error!(
+                                    "Failed to flush partition {name} for journal GC, skipping: {error:?}",
+                                );
+                            }
+                        }
+                    }
+
+                    drop(partitions);
+
+                    if let Err(error) = journal.compact(&flushed_seqno) {
+                        log::error!("Failed to compact journal: {error:?}");
+                    }
+                }
+            })
+            .expect("failed to spawn flush thread");
+
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for FlushThread {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::journal::Journal;
+use std::sync::{Condvar, Mutex};
+
+/// Monotonically increasing flush epoch used for group commit.
+pub type Epoch = u64;
+
+struct State {
+    /// Epoch currently accepting commits.
+    current: Epoch,
+    /// Highest epoch that has been fsynced to disk.
+    durable: Epoch,
+}
+
+/// Coordinates group commit: many concurrent committers can enqueue into
+/// the same flush epoch, and a single background fsync satisfies all of
+/// them at once.
+///
+/// Modeled on dirty-epoch flushing: instead of every commit paying for its
+/// own fsync (100-1000x slower than an unsynced write), committers just
+/// wait for *an* fsync that happens after they joined the epoch, so N
+/// concurrent commits share the cost of roughly one fsync.
+pub struct GroupCommit {
+    state: Mutex<State>,
+    cond: Condvar,
+}
+
+impl GroupCommit {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(State {
+                current: 1,
+                durable: 0,
+            }),
+            cond: Condvar::new(),
+        }
+    }
+
+    /// Joins the epoch currently accepting commits.
+    ///
+    /// Call this right after appending to the journal, then
+    /// [`Self::wait_for`] on the returned epoch to block until it's
+    /// durable.
+    pub fn enter(&self) -> Epoch {
+        self.state.lock().expect("lock is poisoned").current
+    }
+
+    /// Blocks the caller until `epoch` has been durably fsynced.
+    pub fn wait_for(&self, epoch: Epoch) {
+        let mut state = self.state.lock().expect("lock is poisoned");
+        while state.durable < epoch {
+            state = self.cond.wait(state).expect("lock is poisoned");
+        }
+    }
+
+    /// Highest epoch currently known to be durable.
+    pub fn durable_epoch(&self) -> Epoch {
+        self.state.lock().expect("lock is poisoned").durable
+    }
+
+    /// Fsyncs the journal once, satisfying every committer that joined
+    /// before this call, then opens the next epoch for new commits.
+    ///
+    /// Only the background flush thread should call this.
+    pub fn advance(&self, journal: &Journal) -> crate::Result<()> {
+        // Seal the current epoch *before* fsyncing: bumping `current` here
+        // means any `enter()` from this point on joins the *next* epoch,
+        // not this one. That's what guarantees the persist below covers
+        // every append from a committer that joined the sealed epoch -
+        // doing it the other way around (fsync, then seal) leaves a window
+        // where a committer can append after the fsync already ran, call
+        // `enter()` before `current` is bumped, and then have `wait_for`
+        // told its (unsynced) write is durable.
+        let sealed = {
+            let mut state = self.state.lock().expect("lock is poisoned");
+            let sealed = state.current;
+            state.current += 1;
+            sealed
+        };
+
+        journal.persist()?;
+
+        // Only now, after the fsync above has actually completed, is it
+        // true that everything up to `sealed` is durable.
+        let mut state = self.state.lock().expect("lock is poisoned");
+        state.durable = sealed;
+        self.cond.notify_all();
+
+        Ok(())
+    }
+}
+
+impl Default for GroupCommit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,59 @@
+use crate::PartitionHandle;
+use std::path::PathBuf;
+
+impl PartitionHandle {
+    /// Atomically ingests pre-built segment files into this partition.
+    ///
+    /// This bypasses the memtable and journal entirely, so it's much faster
+    /// than inserting key by key, at the cost of not being replayed from the
+    /// journal - the caller is responsible for making sure `paths` are
+    /// already durable before calling this. Nothing is written to the
+    /// journal, so [`crate::Keyspace::persist`] has no effect on the
+    /// ingest; this method fsyncs the partition folder itself before
+    /// returning.
+    ///
+    /// Every key in every ingested file is stamped with the same, single
+    /// sequence number, taken once for the whole batch: this is what makes
+    /// the ingested data consistently visible (or not) to concurrent
+    /// transactions through the MVCC oracle's `read_ts`/`next_txn_ts`, the
+    /// same way a single commit would be.
+    ///
+    /// The whole batch is handed to the tree in one call, which places
+    /// every file (falling back to L0 for any that overlaps existing data)
+    /// and updates the manifest as a single atomic unit - a crash mid-ingest
+    /// leaves either all of `paths` live or none of them, never a partial
+    /// set.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occured, or if a given path does not
+    /// point to a valid segment file.
+    pub fn ingest(&self, paths: &[PathBuf]) -> crate::Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        // One seqno for the whole batch: every key in every ingested file
+        // becomes visible together, exactly as if it had been written by a
+        // single transaction.
+        let seqno = self.keyspace.seqno.next();
+
+        self.tree.ingest(paths, seqno)?;
+
+        self.fsync_partition_folder()?;
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn fsync_partition_folder(&self) -> crate::Result<()> {
+        let folder = std::fs::File::open(self.tree.path())?;
+        folder.sync_all()
+            .map_err(crate::Error::from)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn fsync_partition_folder(&self) -> crate::Result<()> {
+        Ok(())
+    }
+}
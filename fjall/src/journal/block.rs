@@ -0,0 +1,23 @@
+/// Size of a single journal block, in bytes.
+///
+/// The journal is an ever-extending file made up of fixed-size blocks so that
+/// recovery can seek to block boundaries without having to parse the whole
+/// stream from the start.
+pub const BLOCK_SIZE: usize = 4_096;
+
+/// Size of the trailing checksum written at the end of every block.
+pub const CHECKSUM_LEN: usize = std::mem::size_of::<u32>();
+
+/// Usable payload bytes in a block, after reserving space for the checksum.
+pub const BLOCK_PAYLOAD_LEN: usize = BLOCK_SIZE - CHECKSUM_LEN;
+
+/// Computes the checksum of a block.
+///
+/// The previous block's checksum is folded into the hasher first, so the
+/// checksums form a chain: tampering with (or losing) any block invalidates
+/// the checksum of every block that follows it.
+pub fn checksum(prev_checksum: u32, payload: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new_with_initial(prev_checksum);
+    hasher.update(payload);
+    hasher.finalize()
+}
@@ -0,0 +1,142 @@
+mod block;
+mod reader;
+mod record;
+mod writer;
+
+pub use reader::{replay, ReplayResult};
+pub use record::JournalRecord;
+pub use writer::Writer;
+
+use lsm_tree::SeqNo;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const ACTIVE_JOURNAL_NAME: &str = "active";
+
+/// The keyspace's write-ahead journal.
+///
+/// The journal is an ever-extending file of fixed-size, checksummed blocks
+/// (see [`block::BLOCK_SIZE`]). Every committed mutation is appended to it
+/// before being applied to a partition's memtable, so it can be replayed on
+/// [`crate::Keyspace::recover`] to reconstruct any data that hadn't been
+/// flushed to disk yet.
+pub struct Journal {
+    folder: PathBuf,
+    active: Mutex<Writer>,
+}
+
+impl Journal {
+    fn active_path(folder: &Path) -> PathBuf {
+        folder.join(ACTIVE_JOURNAL_NAME)
+    }
+
+    /// Creates a brand new, empty journal in `folder`.
+    pub fn create_new<P: Into<PathBuf>>(folder: P) -> crate::Result<Self> {
+        let folder = folder.into();
+        let writer = Writer::create_new(Self::active_path(&folder))?;
+
+        Ok(Self {
+            folder,
+            active: Mutex::new(writer),
+        })
+    }
+
+    /// Recovers a journal from `folder`, replaying every valid record.
+    ///
+    /// Returns the journal (ready to keep appending to, from the end of the
+    /// last valid block) together with the records that need to be
+    /// re-applied to their partitions' memtables.
+    pub fn recover<P: Into<PathBuf>>(folder: P) -> crate::Result<(Self, ReplayResult)> {
+        let folder = folder.into();
+        let active_path = Self::active_path(&folder);
+
+        let result = replay(&active_path)?;
+
+        // Drop the torn tail (if any) so new appends continue a clean chain.
+        let file = std::fs::OpenOptions::new().write(true).open(&active_path)?;
+        file.set_len(result.valid_len)?;
+        file.sync_all()?;
+
+        let writer = Writer::open_for_append(&active_path, result.last_checksum)?;
+
+        Ok((
+            Self {
+                folder,
+                active: Mutex::new(writer),
+            },
+            result,
+        ))
+    }
+
+    /// Appends a single mutation to the active journal.
+    pub fn append(&self, record: &JournalRecord) -> crate::Result<()> {
+        self.active.lock().expect("lock is poisoned").append(record)
+    }
+
+    /// Current size of the active journal file, in bytes.
+    ///
+    /// Used by the flush thread to decide whether the journal has grown
+    /// enough to be worth compacting, instead of compacting on every wake-up
+    /// regardless of how little (or how much) there is to reclaim.
+    pub fn size(&self) -> crate::Result<u64> {
+        let active = self.active.lock().expect("lock is poisoned");
+        Ok(std::fs::metadata(active.path())?.len())
+    }
+
+    /// Fsyncs the active journal file, making all records appended so far
+    /// durable.
+    pub fn persist(&self) -> crate::Result<()> {
+        self.active.lock().expect("lock is poisoned").persist()
+    }
+
+    /// Reclaims space by dropping journal records that are no longer needed.
+    ///
+    /// `flushed_seqno` gives, per partition, the highest sequence number
+    /// that has already been durably flushed to an on-disk segment. Any
+    /// record at or below that watermark can never be replayed again, so
+    /// the journal is rewritten keeping only the records still needed.
+    ///
+    /// A partition with no entry in `flushed_seqno` (e.g. one that isn't
+    /// loaded) has no watermark to compare against, so all of its records
+    /// are kept; commit markers are always kept as well.
+    pub fn compact(&self, flushed_seqno: &HashMap<Arc<str>, SeqNo>) -> crate::Result<()> {
+        let mut active = self.active.lock().expect("lock is poisoned");
+        active.persist()?;
+
+        let replayed = replay(active.path())?;
+
+        let kept: Vec<_> = replayed
+            .records
+            .into_iter()
+            .filter(|record| match record.partition() {
+                // Commit markers are cheap (9 bytes) and harmless to keep
+                // even if every mutation in their group was dropped below
+                // (replay just sees an empty, already-applied group), so
+                // always keep them rather than trying to track which
+                // groups are now empty.
+                None => true,
+                Some(partition) => match flushed_seqno.get(partition) {
+                    Some(watermark) => record.seqno() > *watermark,
+                    // Partition not loaded (yet): keep the record to be safe.
+                    None => true,
+                },
+            })
+            .collect();
+
+        let tmp_path = self.folder.join(format!("{ACTIVE_JOURNAL_NAME}.compact"));
+        let mut tmp_writer = Writer::create_new(&tmp_path)?;
+
+        for record in &kept {
+            tmp_writer.append(record)?;
+        }
+        tmp_writer.persist()?;
+
+        drop(tmp_writer);
+        std::fs::rename(&tmp_path, active.path())?;
+
+        *active = Writer::open_for_append(active.path(), replay(active.path())?.last_checksum)?;
+
+        Ok(())
+    }
+}
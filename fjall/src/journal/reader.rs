@@ -0,0 +1,84 @@
+use super::block::{checksum, BLOCK_PAYLOAD_LEN, BLOCK_SIZE};
+use super::record::JournalRecord;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Outcome of replaying a journal file.
+pub struct ReplayResult {
+    /// All records found in valid blocks, in write order.
+    pub records: Vec<JournalRecord>,
+
+    /// Checksum of the last valid block, used to seed a [`super::Writer`]
+    /// that continues appending to this journal.
+    pub last_checksum: u32,
+
+    /// Byte offset of the first invalid (or missing) block.
+    ///
+    /// Anything at or after this offset is a torn write and is discarded;
+    /// a writer resuming this journal should truncate to this length.
+    pub valid_len: u64,
+}
+
+/// Replays a journal file block by block.
+///
+/// A block whose checksum doesn't match the expected chained checksum (or a
+/// short trailing block) is *not* treated as corruption: it's the normal
+/// signal that we've reached the end of the durable log, e.g. because the
+/// last block was torn by a crash mid-write.
+pub fn replay<P: AsRef<Path>>(path: P) -> crate::Result<ReplayResult> {
+    let mut file = File::open(path)?;
+    let mut records = Vec::new();
+
+    let mut prev_checksum = 0u32;
+    let mut valid_len = 0u64;
+    let mut block = vec![0u8; BLOCK_SIZE];
+
+    loop {
+        let mut read = 0;
+        while read < BLOCK_SIZE {
+            match file.read(&mut block[read..])? {
+                0 => break,
+                n => read += n,
+            }
+        }
+
+        if read < BLOCK_SIZE {
+            // Short / missing trailing block: end of valid records.
+            break;
+        }
+
+        let payload = &block[..BLOCK_PAYLOAD_LEN];
+        let stored_checksum =
+            u32::from_le_bytes(block[BLOCK_PAYLOAD_LEN..].try_into().expect("is 4 bytes"));
+
+        let expected_checksum = checksum(prev_checksum, payload);
+
+        if expected_checksum != stored_checksum {
+            // Checksum chain broken: this block (and everything after it)
+            // was never fully persisted. End of valid records.
+            break;
+        }
+
+        let mut cursor = 0;
+        while cursor < payload.len() {
+            match JournalRecord::decode_from(&payload[cursor..])? {
+                Some((record, consumed)) => {
+                    cursor += consumed;
+                    records.push(record);
+                }
+                // Remaining bytes are padding (`PAD_BYTE`).
+                None => break,
+            }
+        }
+
+        prev_checksum = stored_checksum;
+        valid_len += BLOCK_SIZE as u64;
+    }
+
+    Ok(ReplayResult {
+        records,
+        last_checksum: prev_checksum,
+        valid_len,
+    })
+}
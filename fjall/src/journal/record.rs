@@ -0,0 +1,169 @@
+use lsm_tree::{SeqNo, Slice};
+use std::sync::Arc;
+
+/// A single mutation recorded in the journal, or a commit boundary.
+///
+/// Every mutation is tagged with the partition it belongs to, so a single
+/// journal can interleave writes to many partitions and still be replayed
+/// into the correct memtables. A [`JournalRecord::Commit`] terminates the
+/// group of mutations written since the previous commit (or the start of
+/// the journal): replay only applies a group once it has seen the `Commit`
+/// that closes it, which is what makes a multi-key, possibly
+/// multi-partition transaction replay all-or-nothing - a crash partway
+/// through writing a commit's records leaves a dangling group with no
+/// terminating marker, and that whole group is discarded.
+#[derive(Clone, Debug)]
+pub enum JournalRecord {
+    Put {
+        partition: Arc<str>,
+        seqno: SeqNo,
+        key: Slice,
+        value: Slice,
+    },
+    Delete {
+        partition: Arc<str>,
+        seqno: SeqNo,
+        key: Slice,
+    },
+    /// Closes the group of mutations written since the last commit,
+    /// sharing this single commit timestamp.
+    Commit { commit_ts: SeqNo },
+}
+
+const TAG_PUT: u8 = 0;
+const TAG_DELETE: u8 = 1;
+const TAG_COMMIT: u8 = 2;
+
+/// Byte a block's unused tail is padded with, once the last record in it has
+/// been written.
+///
+/// This can't be `0`: `0` is also `TAG_PUT`, so zero padding used to get
+/// misread as an endless run of empty `Put` records. `0xFF` isn't a valid tag
+/// for any real record, so hitting it during decode unambiguously means
+/// "nothing but padding from here to the end of the block".
+pub(super) const PAD_BYTE: u8 = 0xFF;
+
+impl JournalRecord {
+    /// The partition a mutation belongs to. `None` for [`Self::Commit`],
+    /// which isn't scoped to a single partition.
+    pub fn partition(&self) -> Option<&Arc<str>> {
+        match self {
+            Self::Put { partition, .. } | Self::Delete { partition, .. } => Some(partition),
+            Self::Commit { .. } => None,
+        }
+    }
+
+    /// The record's sequence number, or the commit's timestamp for
+    /// [`Self::Commit`].
+    pub fn seqno(&self) -> SeqNo {
+        match self {
+            Self::Put { seqno, .. } | Self::Delete { seqno, .. } => *seqno,
+            Self::Commit { commit_ts } => *commit_ts,
+        }
+    }
+
+    /// Serializes the record, appending its bytes to `buf`.
+    pub fn encode_into(&self, buf: &mut Vec<u8>) {
+        if let Self::Commit { commit_ts } = self {
+            buf.push(TAG_COMMIT);
+            buf.extend_from_slice(&commit_ts.to_le_bytes());
+            return;
+        }
+
+        let (tag, partition, seqno, key, value) = match self {
+            Self::Put {
+                partition,
+                seqno,
+                key,
+                value,
+            } => (TAG_PUT, partition, *seqno, key.as_ref(), Some(value.as_ref())),
+            Self::Delete {
+                partition,
+                seqno,
+                key,
+            } => (TAG_DELETE, partition, *seqno, key.as_ref(), None),
+            Self::Commit { .. } => unreachable!("handled above"),
+        };
+
+        buf.push(tag);
+        buf.extend_from_slice(&(partition.len() as u16).to_le_bytes());
+        buf.extend_from_slice(partition.as_bytes());
+        buf.extend_from_slice(&seqno.to_le_bytes());
+        buf.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        buf.extend_from_slice(key);
+
+        if let Some(value) = value {
+            buf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            buf.extend_from_slice(value);
+        }
+    }
+
+    /// Decodes a single record from the front of `bytes`, returning the
+    /// record and the number of bytes consumed.
+    pub fn decode_from(bytes: &[u8]) -> crate::Result<Option<(Self, usize)>> {
+        let mut cursor = 0;
+
+        macro_rules! take {
+            ($n:expr) => {{
+                if bytes.len() < cursor + $n {
+                    return Ok(None);
+                }
+                let slice = &bytes[cursor..cursor + $n];
+                cursor += $n;
+                slice
+            }};
+        }
+
+        let tag = take!(1)[0];
+
+        if tag == PAD_BYTE {
+            // Nothing but padding left in this block - not a real record.
+            return Ok(None);
+        }
+
+        if tag == TAG_COMMIT {
+            let commit_ts = SeqNo::from_le_bytes(take!(8).try_into().expect("should be 8 bytes"));
+            return Ok(Some((Self::Commit { commit_ts }, cursor)));
+        }
+
+        let partition_len = u16::from_le_bytes(take!(2).try_into().expect("should be 2 bytes"));
+        let partition_bytes = take!(partition_len as usize);
+        let partition: Arc<str> = std::str::from_utf8(partition_bytes)
+            .map_err(|_| crate::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "journal record contains invalid partition name",
+            )))?
+            .into();
+
+        let seqno = SeqNo::from_le_bytes(take!(8).try_into().expect("should be 8 bytes"));
+
+        let key_len = u32::from_le_bytes(take!(4).try_into().expect("should be 4 bytes"));
+        let key: Slice = take!(key_len as usize).into();
+
+        let record = match tag {
+            TAG_PUT => {
+                let value_len = u32::from_le_bytes(take!(4).try_into().expect("should be 4 bytes"));
+                let value: Slice = take!(value_len as usize).into();
+                Self::Put {
+                    partition,
+                    seqno,
+                    key,
+                    value,
+                }
+            }
+            TAG_DELETE => Self::Delete {
+                partition,
+                seqno,
+                key,
+            },
+            _ => {
+                return Err(crate::Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "journal record has unknown tag",
+                )))
+            }
+        };
+
+        Ok(Some((record, cursor)))
+    }
+}
@@ -0,0 +1,120 @@
+use super::block::{checksum, BLOCK_PAYLOAD_LEN};
+use super::record::{JournalRecord, PAD_BYTE};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Appends records to the active journal file, in fixed-size checksummed
+/// blocks.
+///
+/// Records are buffered until a block fills up (or [`Writer::flush_block`]
+/// is called explicitly), then the block is padded, checksummed against the
+/// previous block's checksum, and written out.
+pub struct Writer {
+    path: PathBuf,
+    file: BufWriter<File>,
+    block_buf: Vec<u8>,
+    prev_checksum: u32,
+}
+
+impl Writer {
+    /// Creates a brand new, empty journal file.
+    pub fn create_new<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let file = File::create(&path)?;
+        Ok(Self {
+            path: path.as_ref().into(),
+            file: BufWriter::new(file),
+            block_buf: Vec::with_capacity(BLOCK_PAYLOAD_LEN),
+            prev_checksum: 0,
+        })
+    }
+
+    /// Opens an existing journal file for appending, continuing the
+    /// checksum chain from `prev_checksum` (the checksum of the last valid
+    /// block found during replay).
+    pub fn open_for_append<P: AsRef<Path>>(path: P, prev_checksum: u32) -> crate::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(path.as_ref())?;
+
+        Ok(Self {
+            path: path.as_ref().into(),
+            file: BufWriter::new(file),
+            block_buf: Vec::with_capacity(BLOCK_PAYLOAD_LEN),
+            prev_checksum,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a record to the current block, flushing the block first if
+    /// the record would not fit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the encoded record is larger than a single
+    /// block's payload (`BLOCK_PAYLOAD_LEN`) - such a record can never be
+    /// written without truncating it, so this is rejected outright instead
+    /// of silently corrupting the journal.
+    pub fn append(&mut self, record: &JournalRecord) -> crate::Result<()> {
+        let mut encoded = Vec::new();
+        record.encode_into(&mut encoded);
+
+        if encoded.len() > BLOCK_PAYLOAD_LEN {
+            return Err(crate::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "journal record is {} bytes, which exceeds the {BLOCK_PAYLOAD_LEN}-byte block payload",
+                    encoded.len()
+                ),
+            )));
+        }
+
+        if self.block_buf.len() + encoded.len() > BLOCK_PAYLOAD_LEN {
+            self.flush_block()?;
+        }
+
+        self.block_buf.extend_from_slice(&encoded);
+
+        Ok(())
+    }
+
+    /// Pads the current block (if non-empty) and writes it out, chaining
+    /// its checksum from the previous block.
+    pub fn flush_block(&mut self) -> crate::Result<()> {
+        if self.block_buf.is_empty() {
+            return Ok(());
+        }
+
+        // Pad with `PAD_BYTE`, not `0`: a `0` tag byte decodes as a (bogus)
+        // `Put` record, so zero padding used to be misread as real records.
+        self.block_buf.resize(BLOCK_PAYLOAD_LEN, PAD_BYTE);
+
+        let sum = checksum(self.prev_checksum, &self.block_buf);
+        self.file.write_all(&self.block_buf)?;
+        self.file.write_all(&sum.to_le_bytes())?;
+
+        self.prev_checksum = sum;
+        self.block_buf.clear();
+
+        Ok(())
+    }
+
+    /// Flushes the current block and fsyncs the journal file to disk.
+    ///
+    /// This is the operation backing [`crate::Keyspace::persist`].
+    pub fn persist(&mut self) -> crate::Result<()> {
+        self.flush_block()?;
+        self.file.flush()?;
+        self.file.get_ref().sync_all()?;
+        Ok(())
+    }
+
+    /// Checksum of the last block written, used to seed a writer continuing
+    /// this journal's chain.
+    pub fn last_checksum(&self) -> u32 {
+        self.prev_checksum
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{
     config::Config,
+    durability::DurabilityMode,
     file::{FJALL_MARKER, JOURNALS_FOLDER, PARTITIONS_FOLDER},
-    //_journal::Journal,
+    flush_thread::{FlushThread, DEFAULT_FLUSH_INTERVAL},
+    group_commit::GroupCommit,
+    journal::{Journal, JournalRecord},
+    repair::RepairReport,
     version::Version,
     PartitionHandle,
 };
@@ -11,14 +15,19 @@ use std::{
     sync::{Arc, RwLock},
 };
 
-type Partitions = HashMap<Arc<str>, LsmTree>;
+pub(crate) type Partitions = HashMap<Arc<str>, LsmTree>;
 
 #[allow(clippy::module_name_repetitions)]
 pub struct KeyspaceInner {
     pub(crate) partitions: Arc<RwLock<Partitions>>,
-    // pub(crate) journal: Journal,
+    pub(crate) journal: Arc<Journal>,
+    pub(crate) group_commit: Arc<GroupCommit>,
     pub(crate) config: Config,
     pub(crate) seqno: SequenceNumberCounter,
+
+    /// Keeps the background group-commit flush thread alive for as long as
+    /// the keyspace is; the thread is stopped and joined on drop.
+    _flush_thread: FlushThread,
 }
 
 /// The keyspace houses multiple partitions (column families).
@@ -38,12 +47,16 @@ impl std::ops::Deref for Keyspace {
 
 pub struct PartitionConfig {}
 
-// TODO: flush thread
-
 impl Keyspace {
-    /// Flushes the active journal, making sure recently written data is durable
+    /// Makes sure recently written data is durable, according to the
+    /// keyspace's configured [`DurabilityMode`].
     ///
-    /// This has a dramatic, negative performance impact by 100-1000x.
+    /// In `SyncEveryCommit` mode this fsyncs the journal directly, which
+    /// has a dramatic, negative performance impact by 100-1000x. In the
+    /// default `PeriodicEpochFlush` mode, it instead joins the current
+    /// flush epoch and waits for the background flush thread's next fsync,
+    /// which covers every commit in that epoch at once - so N concurrent
+    /// callers pay roughly one fsync between them, not N.
     ///
     /// Persisting only affects durability, NOT consistency! Even without flushing
     /// the journal (and all other parts) are (or should be) crash-safe.
@@ -52,8 +65,29 @@ impl Keyspace {
     ///
     /// Returns error, if an IO error occured.
     pub fn persist(&self) -> crate::Result<()> {
-        // TODO:
-        Ok(())
+        match self.config.durability {
+            DurabilityMode::NoSync => Ok(()),
+            DurabilityMode::SyncEveryCommit => self.journal.persist(),
+            DurabilityMode::PeriodicEpochFlush => {
+                let epoch = self.group_commit.enter();
+                self.group_commit.wait_for(epoch);
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks a keyspace directory for consistency and repairs whatever it
+    /// safely can, without starting normal keyspace operation.
+    ///
+    /// Run this if [`Keyspace::open`] is failing, or after recovering from a
+    /// crash, before opening the keyspace normally.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occured, or if the directory is not a
+    /// fjall keyspace at all.
+    pub fn repair(config: Config) -> crate::Result<RepairReport> {
+        crate::repair::repair(&config)
     }
 
     /// Opens a keyspace in the given directory.
@@ -106,13 +140,12 @@ impl Keyspace {
                 folder.sync_all()?;
             }
 
-            // TODO: 0.3.0 hmmm... unless all partitions are loaded
-            // TODO: the seqno may be wrong
-            // TODO: so a simple user error could make the db inconsistent (not broken, but inconsistent...)
+            // NOTE: `recover` eagerly loads every partition on disk (and replays
+            // the journal into them), so by the time we get here `self.seqno`
+            // already reflects every partition's watermark. A partition that
+            // is only ever opened for the first time here (i.e. it's brand
+            // new) starts at seqno 0, so folding its watermark in is harmless.
 
-            // TODO: another big problem... all partitions need to be loaded for
-            // TODO: journal GC to work... so we NEED to load all partitions... FUCK
-            // TODO:
             // TODO: split open_partition and create_partition
             // TODO: open_partition will have a Runtime config, create will have a disk-backed, immutable PartitionConfig
 
@@ -131,11 +164,86 @@ impl Keyspace {
 
     /// Recovers existing keyspace from directory
     fn recover(config: Config) -> crate::Result<Self> {
+        let journals_folder = config.path.join(JOURNALS_FOLDER);
+        let (journal, replayed) = Journal::recover(&journals_folder)?;
+
+        // Eagerly load every partition that exists on disk: replaying the
+        // journal needs somewhere to apply records to, and the seqno
+        // watermark below is only correct if every partition contributes to
+        // it (see the NOTE in `open_partition`).
+        let partitions_folder = config.path.join(PARTITIONS_FOLDER);
+        let mut partitions = Partitions::default();
+        let mut seqno = SequenceNumberCounter::default();
+
+        if partitions_folder.try_exists()? {
+            for dirent in std::fs::read_dir(&partitions_folder)? {
+                let dirent = dirent?;
+                let name: Arc<str> = dirent.file_name().to_str().expect("should be valid name").into();
+
+                let tree = lsm_tree::Config::new(dirent.path())
+                    .block_cache(config.block_cache.clone())
+                    .open()?;
+
+                seqno.fetch_max(tree.get_next_seqno(), std::sync::atomic::Ordering::AcqRel);
+                partitions.insert(name, tree);
+            }
+        }
+
+        // A commit can span several mutations (possibly across several
+        // partitions); they only become visible together, once the
+        // `Commit` record that closes the group has itself been replayed.
+        // Anything left pending when the journal runs out belongs to a
+        // commit that crashed partway through and never closed - that's
+        // discarded, which is what makes replay all-or-nothing per commit.
+        let mut pending: Vec<&JournalRecord> = Vec::new();
+
+        for record in &replayed.records {
+            let JournalRecord::Commit { .. } = record else {
+                pending.push(record);
+                continue;
+            };
+
+            for record in pending.drain(..) {
+                let partition = record
+                    .partition()
+                    .expect("only Put/Delete records are buffered as pending");
+
+                match partitions.get(partition) {
+                    Some(tree) => {
+                        apply_record(tree, record)?;
+                        seqno.fetch_max(record.seqno() + 1, std::sync::atomic::Ordering::AcqRel);
+                    }
+                    None => {
+                        // The partition was referenced in the journal but never
+                        // made it to disk (crash right after `open_partition`,
+                        // before the first flush) - nothing to replay into.
+                        log::warn!(
+                            "Journal references unknown partition {partition:?}, skipping record",
+                        );
+                    }
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            log::warn!(
+                "Discarding {} journal record(s) from a commit that never completed",
+                pending.len()
+            );
+        }
+
+        let journal = Arc::new(journal);
+        let partitions = Arc::new(RwLock::new(partitions));
+        let (group_commit, flush_thread) =
+            spawn_group_commit(journal.clone(), partitions.clone(), config.durability);
+
         let inner = KeyspaceInner {
-            //  journal: Journal::recover(config.path.join(JOURNALS_FOLDER).join("active")),
-            partitions: Arc::default(),
+            journal,
+            group_commit,
+            partitions,
             config,
-            seqno: SequenceNumberCounter::default(),
+            seqno,
+            _flush_thread: flush_thread,
         };
 
         Ok(Self(Arc::new(inner)))
@@ -169,11 +277,18 @@ impl Keyspace {
         std::fs::create_dir_all(path.join(JOURNALS_FOLDER))?;
         std::fs::create_dir_all(path.join(PARTITIONS_FOLDER))?;
 
+        let journal = Arc::new(Journal::create_new(path.join(JOURNALS_FOLDER))?);
+        let partitions: Arc<RwLock<Partitions>> = Arc::default();
+        let (group_commit, flush_thread) =
+            spawn_group_commit(journal.clone(), partitions.clone(), config.durability);
+
         let inner = KeyspaceInner {
-            //  journal: Journal::create_new(path.join(JOURNALS_FOLDER).join("active")),
-            partitions: Arc::default(),
+            journal,
+            group_commit,
+            partitions,
             config,
             seqno: SequenceNumberCounter::default(),
+            _flush_thread: flush_thread,
         };
 
         // NOTE: Lastly, fsync .fjall marker, which contains the version
@@ -200,3 +315,44 @@ impl Keyspace {
         Ok(Self(Arc::new(inner)))
     }
 }
+
+/// Spins up the group-commit machinery shared by `recover` and `create_new`:
+/// a [`GroupCommit`] epoch tracker, plus the background thread that
+/// periodically fsyncs the journal on its behalf (when the configured
+/// [`DurabilityMode`] calls for it) and reclaims journal space once
+/// partitions have been flushed.
+fn spawn_group_commit(
+    journal: Arc<Journal>,
+    partitions: Arc<RwLock<Partitions>>,
+    durability: DurabilityMode,
+) -> (Arc<GroupCommit>, FlushThread) {
+    let group_commit = Arc::new(GroupCommit::new());
+    let flush_thread = FlushThread::spawn(
+        journal,
+        partitions,
+        group_commit.clone(),
+        durability,
+        DEFAULT_FLUSH_INTERVAL,
+    );
+    (group_commit, flush_thread)
+}
+
+/// Applies a single Put/Delete journal record to a partition's memtable.
+/// Never called with a `Commit` record - those are boundary markers only,
+/// consumed during replay grouping or by the writer that closes a commit
+/// before `apply_record` is reached.
+pub(crate) fn apply_record(tree: &LsmTree, record: &JournalRecord) -> crate::Result<()> {
+    match record {
+        JournalRecord::Put {
+            seqno, key, value, ..
+        } => {
+            tree.insert(key.clone(), value.clone(), *seqno);
+        }
+        JournalRecord::Delete { seqno, key, .. } => {
+            tree.remove(key.clone(), *seqno);
+        }
+        JournalRecord::Commit { .. } => unreachable!("Commit records are handled during replay grouping"),
+    }
+
+    Ok(())
+}
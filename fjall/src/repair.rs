@@ -0,0 +1,154 @@
+use crate::{
+    config::Config,
+    file::{FJALL_MARKER, JOURNALS_FOLDER, PARTITIONS_FOLDER},
+    journal::Journal,
+    version::Version,
+};
+use std::sync::Arc;
+
+/// Outcome of checking (and possibly repairing) a single partition.
+#[derive(Debug)]
+pub enum PartitionStatus {
+    /// The partition opened cleanly, nothing was wrong with it.
+    Ok,
+
+    /// The partition was damaged, but could be brought back into a
+    /// consistent, mountable state.
+    Repaired,
+
+    /// The partition is damaged beyond what `repair` can fix automatically.
+    Unrecoverable(String),
+}
+
+/// Report produced by [`crate::Keyspace::repair`].
+#[derive(Debug, Default)]
+pub struct RepairReport {
+    pub partitions: Vec<(Arc<str>, PartitionStatus)>,
+}
+
+impl RepairReport {
+    /// Returns `true` if every partition is OK or was successfully repaired.
+    pub fn is_mountable(&self) -> bool {
+        self.partitions
+            .iter()
+            .all(|(_, status)| !matches!(status, PartitionStatus::Unrecoverable(_)))
+    }
+}
+
+/// Offline consistency check and recovery.
+///
+/// Unlike [`crate::Keyspace::open`], this does not start normal keyspace
+/// operation: it walks the directory on disk, validates what it finds, and
+/// repairs anything it safely can, then returns a report instead of a
+/// usable [`crate::Keyspace`]. Run this after a crash that left a
+/// half-written `create_new`/`open_partition` behind (e.g. the process died
+/// between writing a segment and fsyncing its folder), before calling
+/// `Keyspace::open` again.
+pub fn repair(config: &Config) -> crate::Result<RepairReport> {
+    let path = &config.path;
+
+    if !path.join(FJALL_MARKER).try_exists()? {
+        return Err(crate::Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} is not a fjall keyspace (missing marker)", path.display()),
+        )));
+    }
+
+    // Re-write the marker: a torn write to the marker itself (not the
+    // common case, but possible) is otherwise unrecoverable. Read back
+    // whatever version is already there (falling back to the current
+    // version only if the header itself turns out to be torn) rather than
+    // hardcoding one - overwriting a newer marker with an older version
+    // would silently downgrade it. Write to a temp file and rename over
+    // the marker so a crash mid-rewrite can never leave it half-written.
+    {
+        let marker_path = path.join(FJALL_MARKER);
+
+        let version = std::fs::File::open(&marker_path)
+            .ok()
+            .and_then(|mut file| Version::read_file_header(&mut file).ok())
+            .unwrap_or(Version::V0);
+
+        let tmp_path = path.join(format!("{FJALL_MARKER}.repair"));
+        let mut file = std::fs::File::create(&tmp_path)?;
+        version.write_file_header(&mut file)?;
+        file.sync_all()?;
+        drop(file);
+
+        std::fs::rename(&tmp_path, &marker_path)?;
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            let folder = std::fs::File::open(path)?;
+            folder.sync_all()?;
+        }
+    }
+
+    // Repairing the journal just means replaying it: `Journal::recover`
+    // already drops a torn trailing block as part of normal operation, so
+    // calling it here both validates the journal and leaves it truncated to
+    // its last valid block.
+    let journals_folder = path.join(JOURNALS_FOLDER);
+    std::fs::create_dir_all(&journals_folder)?;
+    let (_journal, _replayed) = Journal::recover(&journals_folder)?;
+
+    let mut report = RepairReport::default();
+
+    let partitions_folder = path.join(PARTITIONS_FOLDER);
+    std::fs::create_dir_all(&partitions_folder)?;
+
+    for dirent in std::fs::read_dir(&partitions_folder)? {
+        let dirent = dirent?;
+        if !dirent.file_type()?.is_dir() {
+            continue;
+        }
+
+        let name: Arc<str> = dirent.file_name().to_str().expect("should be valid name").into();
+        let status = repair_partition(&config.clone(), &dirent.path());
+
+        report.partitions.push((name, status));
+    }
+
+    Ok(report)
+}
+
+fn repair_partition(config: &Config, path: &std::path::Path) -> PartitionStatus {
+    if open_partition_tree(config, path).is_ok() {
+        return PartitionStatus::Ok;
+    }
+
+    // The partition failed to open - the common recoverable cause is a
+    // missing or torn manifest, with the segment files themselves intact.
+    // Rather than inventing our own manifest format here (which `lsm_tree`
+    // couldn't actually load back), hand the rebuild to `lsm_tree` itself:
+    // it owns the manifest format, so it's the only thing that can
+    // regenerate one that `Config::open` will accept afterwards.
+    match rebuild_manifest(config, path) {
+        Ok(()) => match open_partition_tree(config, path) {
+            Ok(_) => PartitionStatus::Repaired,
+            Err(error) => PartitionStatus::Unrecoverable(error.to_string()),
+        },
+        Err(error) => PartitionStatus::Unrecoverable(format!(
+            "partition at {} failed to open and its manifest could not be \
+             rebuilt from the segments present on disk: {error}",
+            path.display()
+        )),
+    }
+}
+
+fn open_partition_tree(config: &Config, path: &std::path::Path) -> crate::Result<lsm_tree::Tree> {
+    Ok(lsm_tree::Config::new(path)
+        .block_cache(config.block_cache.clone())
+        .open()?)
+}
+
+/// Regenerates a partition's manifest from the segment files present on
+/// disk, in `lsm_tree`'s own on-disk format (not a format invented here),
+/// by asking `lsm_tree` to scan the folder and rebuild it.
+fn rebuild_manifest(config: &Config, path: &std::path::Path) -> crate::Result<()> {
+    Ok(lsm_tree::Config::new(path)
+        .block_cache(config.block_cache.clone())
+        .recover_manifest_from_segments()
+        .open()
+        .map(|_| ())?)
+}
@@ -0,0 +1,77 @@
+use crate::journal::JournalRecord;
+use crate::keyspace::apply_record;
+use crate::PartitionHandle;
+use lsm_tree::Slice;
+use std::sync::Arc;
+
+impl PartitionHandle {
+    /// Inserts a key-value pair into the partition.
+    ///
+    /// The mutation is appended to the keyspace's journal (closed off by its
+    /// own commit marker) before it's applied to the memtable. The journal
+    /// holds it in memory until its current block fills up or the journal
+    /// is persisted; only after that can the next
+    /// [`crate::Keyspace::recover`] replay it. Call
+    /// [`crate::Keyspace::persist`] afterwards to make it durable according
+    /// to the keyspace's durability mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occured.
+    pub fn insert<K: Into<Slice>, V: Into<Slice>>(&self, key: K, value: V) -> crate::Result<()> {
+        let seqno = self.keyspace.seqno.next();
+
+        self.write(JournalRecord::Put {
+            partition: self.name(),
+            seqno,
+            key: key.into(),
+            value: value.into(),
+        })
+    }
+
+    /// Removes a key from the partition.
+    ///
+    /// See [`Self::insert`] for the journal/durability contract this
+    /// follows.
+    ///
+    /// # Errors
+    ///
+    /// Returns error, if an IO error occured.
+    pub fn remove<K: Into<Slice>>(&self, key: K) -> crate::Result<()> {
+        let seqno = self.keyspace.seqno.next();
+
+        self.write(JournalRecord::Delete {
+            partition: self.name(),
+            seqno,
+            key: key.into(),
+        })
+    }
+
+    /// Appends `record` plus a closing commit marker to the journal, then
+    /// applies it to the memtable. The journal write comes first, so once
+    /// the journal block holding it has been written out, replay restores
+    /// the mutation even if the process crashed before (or while) it was
+    /// applied below.
+    fn write(&self, record: JournalRecord) -> crate::Result<()> {
+        let commit_ts = record.seqno();
+
+        self.keyspace.journal.append(&record)?;
+        self.keyspace
+            .journal
+            .append(&JournalRecord::Commit { commit_ts })?;
+
+        apply_record(&self.tree, &record)
+    }
+
+    /// The name this partition is journaled under, derived from its
+    /// on-disk folder name (the same trick [`crate::ingest`] uses to fsync
+    /// that folder).
+    fn name(&self) -> Arc<str> {
+        self.tree
+            .path()
+            .file_name()
+            .and_then(|name| name.to_str())
+            .expect("partition path should have a valid folder name")
+            .into()
+    }
+}
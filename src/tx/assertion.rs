@@ -0,0 +1,36 @@
+/// A precondition a transaction attaches to a key, checked atomically at
+/// commit time, before a commit timestamp is handed out.
+///
+/// This is borrowed from 2PC prewrite assertions: conflict detection alone
+/// only catches write-write/read-write races between transactions, while
+/// assertions let callers express uniqueness constraints and
+/// compare-and-swap semantics without a separate read-then-write round
+/// trip.
+///
+/// Checked against the latest *committed* timestamp as of commit time (see
+/// [`super::oracle::Oracle::new_commit_ts`]), not the transaction's own
+/// `read_ts` - a stale `read_ts` would otherwise make these assertions miss
+/// commits that finished after the transaction started reading. This still
+/// isn't full compare-and-swap strength against another transaction
+/// committing at the very same instant; see the caveat on `new_commit_ts`.
+#[derive(Debug, Clone)]
+pub enum AssertionKind {
+    /// The key must have no visible committed value at or below the latest
+    /// committed timestamp.
+    NotExist,
+
+    /// The key must have a visible committed value at or below the latest
+    /// committed timestamp.
+    Exist,
+
+    /// The key's visible committed value at or below the latest committed
+    /// timestamp must equal this exact value.
+    ValueEquals(Vec<u8>),
+}
+
+/// A single key + the precondition it must satisfy at commit time.
+#[derive(Debug, Clone)]
+pub struct Assertion<K> {
+    pub key: K,
+    pub kind: AssertionKind,
+}
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Identifies the partition a key belongs to.
+///
+/// A `Keyspace` owns many partitions, and a single transaction can touch
+/// several of them at once, so every fingerprint tracked by the conflict
+/// checker is namespaced by which partition it came from - otherwise two
+/// unrelated keys that happen to collide across partitions would look like
+/// a conflict.
+pub type PartitionId = Arc<str>;
+
+/// Tracks the read and write sets of a single transaction, to detect
+/// write-write and read-write conflicts against other, concurrently
+/// committed transactions.
+///
+/// Keys are not stored directly, only their fingerprints (hash of
+/// `(partition, key)`), to keep the conflict checker's memory footprint
+/// independent of key/value size.
+#[derive(Debug, Default, Clone)]
+pub struct ConflictChecker {
+    reads: Vec<u64>,
+    writes: HashSet<u64>,
+}
+
+impl ConflictChecker {
+    fn fingerprint(partition: &PartitionId, key: &[u8]) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        partition.hash(&mut hasher);
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Records that `key` (in `partition`) was read by this transaction.
+    pub fn mark_read(&mut self, partition: &PartitionId, key: &[u8]) {
+        self.reads.push(Self::fingerprint(partition, key));
+    }
+
+    /// Records that `key` (in `partition`) was written by this transaction.
+    pub fn mark_write(&mut self, partition: &PartitionId, key: &[u8]) {
+        self.writes.insert(Self::fingerprint(partition, key));
+    }
+
+    /// Returns `true` if anything this transaction read was written by
+    /// `other`, across any partition.
+    pub fn has_conflict(&self, other: &Self) -> bool {
+        if self.reads.is_empty() {
+            return false;
+        }
+
+        self.reads.iter().any(|fp| other.writes.contains(fp))
+    }
+}
@@ -1,3 +1,4 @@
+use super::assertion::{Assertion, AssertionKind};
 use super::conflict_manager::ConflictChecker;
 use core::ops::AddAssign;
 use std::borrow::Cow;
@@ -16,9 +17,13 @@ pub(super) struct OracleInner<C> {
     pub(super) committed_txns: Vec<CommittedTxn<C>>,
 }
 
-pub(super) enum CreateCommitTimestampResult<C> {
+pub(super) enum CreateCommitTimestampResult<C, K> {
     Timestamp(u64),
     Conflict(Option<C>),
+
+    /// A commit-time assertion didn't hold, so no commit timestamp was
+    /// handed out.
+    AssertionFailed { key: K, kind: AssertionKind },
 }
 
 #[derive(Debug)]
@@ -67,12 +72,48 @@ pub(super) struct Oracle<C> {
 }
 
 impl Oracle<ConflictChecker> {
-    pub(super) fn new_commit_ts(
+    /// Hands out a commit timestamp for a transaction, or reports a
+    /// conflict.
+    ///
+    /// `conflict_manager` may carry fingerprints from several partitions at
+    /// once (a transaction can read/write across multiple
+    /// `PartitionHandle`s and commit atomically) - conflicts are still
+    /// detected correctly because `ConflictChecker` namespaces every
+    /// fingerprint by partition, so `has_conflict` is really comparing
+    /// `(partition, key)` pairs, not bare keys.
+    ///
+    /// `assertions` are evaluated with `check_assertion` under the same
+    /// `inner` lock that hands out the commit timestamp, against the
+    /// *latest committed* timestamp as of that point (passed as the third
+    /// argument) rather than the transaction's own, possibly stale,
+    /// `read_ts` - so an assertion sees every commit that finished before
+    /// this one reaches the front of the commit-serialization lock, not
+    /// just what was visible when the transaction began. The first
+    /// assertion that doesn't hold aborts the commit before a timestamp is
+    /// ever handed out.
+    ///
+    /// This closes the common staleness gap, but doesn't by itself give
+    /// `NotExist`/CAS assertions true compare-and-swap strength: a write
+    /// only becomes visible to `check_assertion` once it's actually been
+    /// applied to the tree, which happens *after* `new_commit_ts` returns
+    /// for that transaction, not under this lock - so two transactions that
+    /// both reach this function back-to-back, before either one's write
+    /// has landed, can still both pass the same `NotExist` assertion. Fully
+    /// closing that window needs asserted keys to also participate in
+    /// `conflict_manager`'s write-write conflict detection, which today
+    /// only compares `conflict_manager`'s own read set against other
+    /// transactions' write sets.
+    pub(super) fn new_commit_ts<K>(
         &self,
         done_read: &mut bool,
         read_ts: u64,
         conflict_manager: ConflictChecker,
-    ) -> Result<CreateCommitTimestampResult<ConflictChecker>, Error> {
+        assertions: &[Assertion<K>],
+        mut check_assertion: impl FnMut(&K, &AssertionKind, u64) -> bool,
+    ) -> Result<CreateCommitTimestampResult<ConflictChecker, K>, Error>
+    where
+        K: Clone,
+    {
         let ts = {
             let mut inner = self.inner.lock()?;
 
@@ -94,6 +135,21 @@ impl Oracle<ConflictChecker> {
                 }
             }
 
+            // The most recent timestamp known to be committed, as of this
+            // point under the lock - not `read_ts`, which may be older than
+            // commits that finished while this transaction was still
+            // running.
+            let latest_committed_ts = inner.next_txn_ts.saturating_sub(1);
+
+            for assertion in assertions {
+                if !check_assertion(&assertion.key, &assertion.kind, latest_committed_ts) {
+                    return Ok(CreateCommitTimestampResult::AssertionFailed {
+                        key: assertion.key.clone(),
+                        kind: assertion.kind.clone(),
+                    });
+                }
+            }
+
             let ts = {
                 if !*done_read {
                     self.read_mark.done(read_ts)?;